@@ -0,0 +1,307 @@
+//! Async (non-blocking) variant of [`NeocitiesClient`](crate::NeocitiesClient).
+//!
+//! [`AsyncNeocitiesClient`] mirrors the blocking client's full method surface, but is built on
+//! the async `reqwest::Client` and returns futures instead of blocking the calling thread.
+//! Response parsing, auth and retry logic are shared with the blocking client, so the two behave
+//! identically beyond how they're awaited.
+//!
+//! ```no_run
+//! # async fn go() -> Result<(), rs_neocities::NeocitiesError> {
+//! # let key = String::new();
+//! use rs_neocities::async_client::AsyncNeocitiesClient;
+//!
+//! let c = AsyncNeocitiesClient::new_with_key(&key);
+//! let info = c.info().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+use std::time::Duration;
+
+use reqwest::{Client, RequestBuilder, Response, multipart::{Part, Form}};
+
+use crate::{
+    NeocitiesError, InfoResponse, UploadResponse, DeleteResponse, KeyResponse,
+    ListResponse, parse_response, parse_list_response, backoff_duration, retry_after_from_headers, url_for,
+    ClientConfig, ClientConfigBuilder, AuthRequestBuilder, apply_auth
+};
+
+impl AuthRequestBuilder for RequestBuilder {
+    fn bearer_auth_impl(self, token: &str) -> Self { self.bearer_auth(token) }
+    fn basic_auth_impl(self, username: &str, password: &str) -> Self { self.basic_auth(username, Some(password)) }
+}
+
+/// Async variant of [`NeocitiesClient`](crate::NeocitiesClient). See the module docs for details.
+#[derive(Debug)]
+pub struct AsyncNeocitiesClient {
+    client: Client,
+    config: ClientConfig
+}
+
+impl AsyncNeocitiesClient {
+    /// Creates a client with a username and password. See [`NeocitiesClient::new`](crate::NeocitiesClient::new).
+    pub fn new(username: &str, password: &str) -> AsyncNeocitiesClient {
+        AsyncNeocitiesClient { client: Client::new(), config: ClientConfig::new(username, password) }
+    }
+    /// Creates a client with an API key. See [`NeocitiesClient::new_with_key`](crate::NeocitiesClient::new_with_key).
+    pub fn new_with_key(key: &str) -> AsyncNeocitiesClient {
+        AsyncNeocitiesClient { client: Client::new(), config: ClientConfig::new_with_key(key) }
+    }
+    /// Creates a client with no authentication. See [`NeocitiesClient::new_no_auth`](crate::NeocitiesClient::new_no_auth).
+    pub fn new_no_auth() -> AsyncNeocitiesClient {
+        AsyncNeocitiesClient { client: Client::new(), config: ClientConfig::new_no_auth() }
+    }
+
+    /// See [`NeocitiesClient::max_retries`](crate::NeocitiesClient::max_retries).
+    pub fn max_retries(mut self, max_retries: u32) -> AsyncNeocitiesClient {
+        self.config.max_retries = max_retries;
+        self
+    }
+    /// See [`NeocitiesClient::base_backoff`](crate::NeocitiesClient::base_backoff).
+    pub fn base_backoff(mut self, base_backoff: Duration) -> AsyncNeocitiesClient {
+        self.config.base_backoff = base_backoff;
+        self
+    }
+    /// See [`NeocitiesClient::retry_on_server_errors`](crate::NeocitiesClient::retry_on_server_errors).
+    pub fn retry_on_server_errors(mut self, retry: bool) -> AsyncNeocitiesClient {
+        self.config.retry_5xx_on_mutations = retry;
+        self
+    }
+
+    fn get_auth(&self, req: RequestBuilder) -> Result<RequestBuilder, NeocitiesError> {
+        apply_auth(&self.config, req)
+    }
+
+    /// As `NeocitiesClient::send_with_retry`, but `.send()`s asynchronously.
+    async fn send_with_retry(&self, build: impl Fn() -> Result<RequestBuilder, NeocitiesError>, retry_5xx: bool) -> Result<Response, NeocitiesError> {
+        let mut attempt = 0;
+        loop {
+            match build()?.send().await {
+                Ok(resp) => {
+                    let status = resp.status();
+                    let should_retry = status.as_u16() == 429 || (retry_5xx && status.is_server_error());
+                    if should_retry && attempt < self.config.max_retries {
+                        let wait = retry_after_from_headers(resp.headers()).unwrap_or_else(|| backoff_duration(self.config.base_backoff, attempt));
+                        attempt += 1;
+                        tokio::time::sleep(wait).await;
+                        continue
+                    }
+                    if should_retry {
+                        // retries exhausted on a 429/5xx: surface it the same way a connection error would be
+                        return Err(resp.error_for_status().unwrap_err().into())
+                    }
+                    return Ok(resp)
+                }
+                Err(e) => {
+                    if attempt < self.config.max_retries {
+                        let wait = backoff_duration(self.config.base_backoff, attempt);
+                        attempt += 1;
+                        tokio::time::sleep(wait).await;
+                        continue
+                    }
+                    return Err(NeocitiesError::RequestError(e))
+                }
+            }
+        }
+    }
+    async fn get(&self, endpoint: &str) -> Result<String, NeocitiesError> {
+        let url = url_for(&self.config.base_url, endpoint);
+        let resp = self.send_with_retry(|| self.get_auth(self.client.get(url.as_str())), true).await?;
+        Ok(resp.text().await?)
+    }
+
+    /// Gets info about the auth user's site.
+    pub async fn info(&self) -> Result<InfoResponse, NeocitiesError> {
+        parse_response(&self.info_raw().await?)
+    }
+    /// As `info()`, but returns the raw JSON text instead of a parsed [`InfoResponse`].
+    pub async fn info_raw(&self) -> Result<String, NeocitiesError> {
+        self.get("info").await
+    }
+    /// Gets info about the given site.
+    ///
+    /// If the site doesn't exist, Neocities reports an error and this returns a [`NeocitiesError::ApiError`].
+    pub async fn info_no_auth(&self, site_name: &str) -> Result<InfoResponse, NeocitiesError> {
+        parse_response(&self.info_no_auth_raw(site_name).await?)
+    }
+    /// As `info_no_auth()`, but returns the raw JSON text instead of a parsed [`InfoResponse`].
+    pub async fn info_no_auth_raw(&self, site_name: &str) -> Result<String, NeocitiesError> {
+        let url = url_for(&self.config.base_url, &format!("info?sitename={}", site_name)); // doesn't need auth, just send it raw
+        let resp = self.send_with_retry(|| Ok(self.client.get(url.as_str())), true).await?;
+        Ok(resp.text().await?)
+    }
+
+    /// Lists all files and directories on the auth user's site.
+    pub async fn list_all(&self) -> Result<ListResponse, NeocitiesError> {
+        parse_list_response(&self.list_all_raw().await?)
+    }
+    /// As `list_all()`, but returns the raw JSON text instead of a parsed [`ListResponse`].
+    pub async fn list_all_raw(&self) -> Result<String, NeocitiesError> {
+        self.get("list").await
+    }
+    /// Lists files and directories starting from the specified path.
+    pub async fn list(&self, path: &str) -> Result<ListResponse, NeocitiesError> {
+        parse_list_response(&self.list_raw(path).await?)
+    }
+    /// As `list()`, but returns the raw JSON text instead of a parsed [`ListResponse`].
+    pub async fn list_raw(&self, path: &str) -> Result<String, NeocitiesError> {
+        self.get(&format!("list?path={}", path)).await
+    }
+
+    /// Uploads a local file to the site, placing it at `remote_path` relative to the site root.
+    pub async fn upload(&self, local_path: impl AsRef<Path>, remote_path: &str) -> Result<UploadResponse, NeocitiesError> {
+        let v = vec![(local_path, remote_path)];
+        self.upload_multiple(&v).await
+    }
+    /// As `upload()`, but returns the raw JSON text instead of a parsed [`UploadResponse`].
+    pub async fn upload_raw(&self, local_path: impl AsRef<Path>, remote_path: &str) -> Result<String, NeocitiesError> {
+        let v = vec![(local_path, remote_path)];
+        self.upload_multiple_raw(&v).await
+    }
+    /// Uploads multiple local files to the site. Path tuples should take the form `(local, remote)`,
+    /// where `local` is the local path, and `remote` is the desired remote path relative to the root.
+    ///
+    /// Unlike the blocking client, the local files are read concurrently.
+    pub async fn upload_multiple(&self, paths: &[(impl AsRef<Path>, &str)]) -> Result<UploadResponse, NeocitiesError> {
+        parse_response(&self.upload_multiple_raw(paths).await?)
+    }
+    /// As `upload_multiple()`, but returns the raw JSON text instead of a parsed [`UploadResponse`].
+    pub async fn upload_multiple_raw(&self, paths: &[(impl AsRef<Path>, &str)]) -> Result<String, NeocitiesError> {
+        let reads = paths.iter().map(|(local, remote)| async move {
+            Ok::<(Vec<u8>, String), NeocitiesError>((tokio::fs::read(local).await?, String::from(*remote)))
+        });
+        let files = futures::future::try_join_all(reads).await?;
+
+        self.upload_bytes_multiple_raw(files).await
+    }
+    /// Uploads a vector of bytes to the site as a file, placing it at `remote_path` relative to the site root.
+    pub async fn upload_bytes(&self, bytes: Vec<u8>, remote_path: &str) -> Result<UploadResponse, NeocitiesError> {
+        let v = vec![(bytes, remote_path)];
+        self.upload_bytes_multiple(v).await
+    }
+    /// As `upload_bytes()`, but returns the raw JSON text instead of a parsed [`UploadResponse`].
+    pub async fn upload_bytes_raw(&self, bytes: Vec<u8>, remote_path: &str) -> Result<String, NeocitiesError> {
+        let v = vec![(bytes, remote_path)];
+        self.upload_bytes_multiple_raw(v).await
+    }
+    /// Uploads multiple vectors of bytes to the site as files.
+    /// Tuples should take the form `(data, remote)`, where `data` is the data,
+    /// and `remote` is the desired remote path relative to the root.
+    pub async fn upload_bytes_multiple(&self, bytes: Vec<(Vec<u8>, impl AsRef<str>)>) -> Result<UploadResponse, NeocitiesError> {
+        parse_response(&self.upload_bytes_multiple_raw(bytes).await?)
+    }
+    /// As `upload_bytes_multiple()`, but returns the raw JSON text instead of a parsed [`UploadResponse`].
+    pub async fn upload_bytes_multiple_raw(&self, bytes: Vec<(Vec<u8>, impl AsRef<str>)>) -> Result<String, NeocitiesError> {
+        // uploads aren't idempotent, so each retry attempt needs its own fresh copy of the data to build a new request from
+        let bytes: Vec<(Vec<u8>, String)> = bytes.into_iter().map(|(data, path)| (data, String::from(path.as_ref()))).collect();
+
+        let resp = self.send_with_retry(|| {
+            let mut form = Form::new();
+            for (data, path) in bytes.clone() {
+                let part = Part::bytes(data).file_name(path);
+                form = form.part("", part)
+            }
+            self.get_auth(self.client.post(url_for(&self.config.base_url, "upload")).multipart(form))
+        }, self.config.retry_5xx_on_mutations).await?;
+
+        Ok(resp.text().await?)
+    }
+
+    /// Delete a file on the site. `path` is from the site root.
+    pub async fn delete(&self, path: &str) -> Result<DeleteResponse, NeocitiesError> {
+        let v = vec![path];
+        self.delete_multiple(v).await
+    }
+    /// As `delete()`, but returns the raw JSON text instead of a parsed [`DeleteResponse`].
+    pub async fn delete_raw(&self, path: &str) -> Result<String, NeocitiesError> {
+        let v = vec![path];
+        self.delete_multiple_raw(v).await
+    }
+    /// Delete multiple files.
+    pub async fn delete_multiple(&self, files: Vec<&str>) -> Result<DeleteResponse, NeocitiesError> {
+        parse_response(&self.delete_multiple_raw(files).await?)
+    }
+    /// As `delete_multiple()`, but returns the raw JSON text instead of a parsed [`DeleteResponse`].
+    pub async fn delete_multiple_raw(&self, files: Vec<&str>) -> Result<String, NeocitiesError> {
+        let resp = self.send_with_retry(|| {
+            let mut req = self.get_auth(self.client.post(url_for(&self.config.base_url, "delete")))?;
+            for f in &files {
+                req = req.query(&[("filenames[]", f)]);
+            }
+            Ok(req)
+        }, self.config.retry_5xx_on_mutations).await?;
+
+        Ok(resp.text().await?)
+    }
+
+    /// Gets the API key for the auth user.
+    pub async fn get_key(&self) -> Result<KeyResponse, NeocitiesError> {
+        parse_response(&self.get_key_raw().await?)
+    }
+    /// As `get_key()`, but returns the raw JSON text instead of a parsed [`KeyResponse`].
+    pub async fn get_key_raw(&self) -> Result<String, NeocitiesError> {
+        self.get("key").await
+    }
+}
+
+/// Builds an [`AsyncNeocitiesClient`] with a configurable base URL and `User-Agent`.
+/// See [`NeocitiesClientBuilder`](crate::NeocitiesClientBuilder) for details.
+#[derive(Debug)]
+pub struct AsyncNeocitiesClientBuilder {
+    inner: ClientConfigBuilder
+}
+
+impl AsyncNeocitiesClientBuilder {
+    /// Starts building a client with a username and password. See [`AsyncNeocitiesClient::new`].
+    pub fn new(username: &str, password: &str) -> AsyncNeocitiesClientBuilder {
+        AsyncNeocitiesClientBuilder { inner: ClientConfigBuilder::new(username, password) }
+    }
+    /// Starts building a client with an API key. See [`AsyncNeocitiesClient::new_with_key`].
+    pub fn new_with_key(key: &str) -> AsyncNeocitiesClientBuilder {
+        AsyncNeocitiesClientBuilder { inner: ClientConfigBuilder::new_with_key(key) }
+    }
+    /// Starts building a client with no authentication. See [`AsyncNeocitiesClient::new_no_auth`].
+    pub fn new_no_auth() -> AsyncNeocitiesClientBuilder {
+        AsyncNeocitiesClientBuilder { inner: ClientConfigBuilder::new_no_auth() }
+    }
+
+    /// Sets the base URL API requests are made against. Defaults to `https://neocities.org`.
+    pub fn hostname(mut self, hostname: &str) -> AsyncNeocitiesClientBuilder {
+        self.inner = self.inner.hostname(hostname);
+        self
+    }
+    /// Sets a custom `User-Agent` header to send with every request, instead of reqwest's default.
+    pub fn user_agent(mut self, user_agent: &str) -> AsyncNeocitiesClientBuilder {
+        self.inner = self.inner.user_agent(user_agent);
+        self
+    }
+    /// See [`AsyncNeocitiesClient::max_retries`].
+    pub fn max_retries(mut self, max_retries: u32) -> AsyncNeocitiesClientBuilder {
+        self.inner = self.inner.max_retries(max_retries);
+        self
+    }
+    /// See [`AsyncNeocitiesClient::base_backoff`].
+    pub fn base_backoff(mut self, base_backoff: Duration) -> AsyncNeocitiesClientBuilder {
+        self.inner = self.inner.base_backoff(base_backoff);
+        self
+    }
+    /// See [`AsyncNeocitiesClient::retry_on_server_errors`].
+    pub fn retry_on_server_errors(mut self, retry: bool) -> AsyncNeocitiesClientBuilder {
+        self.inner = self.inner.retry_on_server_errors(retry);
+        self
+    }
+
+    /// Builds the client. Fails only if the given `User-Agent` isn't a valid header value.
+    pub fn build(self) -> Result<AsyncNeocitiesClient, NeocitiesError> {
+        let (config, user_agent) = self.inner.into_parts();
+        let mut builder = Client::builder();
+        if let Some(ua) = &user_agent {
+            builder = builder.user_agent(ua);
+        }
+        Ok(AsyncNeocitiesClient {
+            client: builder.build()?,
+            config
+        })
+    }
+}
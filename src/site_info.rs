@@ -23,13 +23,11 @@
 //! The [`SiteInfo`] struct also provides more general methods for getting files and directories on the site.
 //! There's also a few functions for getting hashes in the same format neocities provides them.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::collections::HashSet;
 use std::io;
 use std::fs::read;
 
-use serde_json::{Value, from_str};
-use chrono::Utc;
-use chrono;
 use sha1::{Sha1, Digest};
 
 use crate::{NeocitiesClient, NeocitiesError};
@@ -63,13 +61,7 @@ impl SiteInfo {
     /// 
     /// Returns an error if the HTTP request fails or if the API call somehow returns malformed or invalid JSON.
     pub fn refresh(&mut self) -> Result<(), NeocitiesError> {
-        let list = from_str::<Value>(&self.client.list_all()?).unwrap();
-
-        let mut items = Vec::new(); // clear local cache
-        for entry in list.get("files").unwrap().as_array().unwrap() { // go through list and parse
-            items.push(SiteItem::from_json(entry)?)
-        }
-        self.items = items;
+        self.items = self.client.list_all()?.files;
 
         Ok(())
     }
@@ -130,88 +122,300 @@ impl SiteInfo {
             None => true
         }
     }
+
+    /// Mirrors a local directory to the site, uploading only files whose hash differs from what's
+    /// already there (see [`SiteInfo::file_changed`]), and optionally deleting remote files under
+    /// `remote_root` that no longer exist locally.
+    ///
+    /// `local_root` is walked recursively; each local file maps to `remote_root` joined with its
+    /// path relative to `local_root`. Files matched by a pattern in `opts.ignore`, or listed
+    /// (one glob pattern per line) in a `.neocitiesignore` file at the root of `local_root`,
+    /// are skipped entirely.
+    ///
+    /// If `opts.dry_run` is set, nothing is uploaded or deleted; the actions that *would* have
+    /// been taken are returned instead, so callers can show a preview before committing to it.
+    ///
+    /// Returns an error if a local file can't be read, or if the upload/delete API calls fail.
+    pub fn sync_dir(&mut self, local_root: impl AsRef<Path>, remote_root: &str, opts: &SyncOptions) -> Result<Vec<SyncAction>, NeocitiesError> {
+        let local_root = local_root.as_ref();
+        let ignore = load_ignore_patterns(local_root, opts);
+
+        let mut local_files = Vec::new();
+        walk_dir(local_root, local_root, &ignore, &mut local_files)?;
+
+        let mut actions = Vec::new();
+        let mut to_upload: Vec<(PathBuf, String)> = Vec::new();
+        let mut remote_paths_seen = HashSet::new();
+
+        for local_path in &local_files {
+            let rel = local_path.strip_prefix(local_root).unwrap();
+            let remote_path = join_remote(remote_root, rel);
+
+            if self.file_changed(local_path, &remote_path)? {
+                actions.push(SyncAction::Upload(remote_path.clone()));
+                to_upload.push((local_path.clone(), remote_path.clone()));
+            }
+            else {
+                actions.push(SyncAction::Skip(remote_path.clone()));
+            }
+            remote_paths_seen.insert(remote_path);
+        }
+
+        if !opts.dry_run && !to_upload.is_empty() {
+            let refs: Vec<(&Path, &str)> = to_upload.iter().map(|(l, r)| (l.as_path(), r.as_str())).collect();
+            self.client.upload_multiple(&refs)?;
+        }
+
+        if opts.prune {
+            let to_delete: Vec<String> = self.items.iter()
+                .filter(|i| matches!(i, SiteItem::File(_)))
+                .map(|i| i.get_path())
+                .filter(|p| path_under(p, remote_root) && !remote_paths_seen.contains(*p))
+                .map(String::from)
+                .collect();
+
+            for path in &to_delete {
+                actions.push(SyncAction::Delete(path.clone()));
+            }
+
+            if !opts.dry_run && !to_delete.is_empty() {
+                let refs: Vec<&str> = to_delete.iter().map(String::as_str).collect();
+                self.client.delete_multiple(refs)?;
+            }
+        }
+
+        if !opts.dry_run {
+            self.refresh()?;
+        }
+
+        Ok(actions)
+    }
+}
+
+/// Options controlling a [`SiteInfo::sync_dir`] run.
+#[derive(Debug, Default, Clone)]
+pub struct SyncOptions {
+    /// Delete remote files under `remote_root` that have no local counterpart.
+    pub prune: bool,
+    /// Extra glob ignore patterns, matched against each file's path (relative to `local_root`)
+    /// and its bare file name. Combined with any patterns found in a `.neocitiesignore` file.
+    pub ignore: Vec<String>,
+    /// Don't upload or delete anything; just report what would happen.
+    pub dry_run: bool
+}
+
+/// A single action taken (or, in dry-run mode, planned) by [`SiteInfo::sync_dir`].
+/// The `String` in each variant is the affected remote path.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SyncAction {
+    Upload(String),
+    Delete(String),
+    Skip(String)
+}
+
+fn load_ignore_patterns(local_root: &Path, opts: &SyncOptions) -> Vec<String> {
+    let mut patterns = opts.ignore.clone();
+    if let Ok(contents) = std::fs::read_to_string(local_root.join(".neocitiesignore")) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                patterns.push(line.to_string());
+            }
+        }
+    }
+    patterns
+}
+
+fn walk_dir(root: &Path, dir: &Path, ignore: &[String], out: &mut Vec<PathBuf>) -> Result<(), NeocitiesError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let rel = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if ignore.iter().any(|pat| glob_match(pat, &rel) || glob_match(pat, &name)) {
+            continue
+        }
+
+        if path.is_dir() {
+            walk_dir(root, &path, ignore, out)?;
+        }
+        else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `remote_root` joined with a path relative to the local sync root, normalised to always
+/// start with a single leading `/` (matching the paths [`SiteItem`] uses).
+fn join_remote(remote_root: &str, rel: &Path) -> String {
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    let root = remote_root.trim_matches('/');
+    if root.is_empty() {
+        format!("/{}", rel)
+    }
+    else {
+        format!("/{}/{}", root, rel)
+    }
+}
+
+/// Whether `path` (a `/`-prefixed remote path) sits under `remote_root`.
+fn path_under(path: &str, remote_root: &str) -> bool {
+    let root = remote_root.trim_matches('/');
+    if root.is_empty() {
+        true
+    }
+    else {
+        path.starts_with(&format!("/{}/", root))
+    }
+}
+
+/// A small hand-rolled glob matcher supporting `*` (any run of characters) and `?` (any single
+/// character), matched against the whole string.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn inner(p: &[u8], t: &[u8]) -> bool {
+        match (p.first(), t.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => inner(&p[1..], t) || (!t.is_empty() && inner(p, &t[1..])),
+            (Some(b'?'), Some(_)) => inner(&p[1..], &t[1..]),
+            (Some(pc), Some(tc)) if pc == tc => inner(&p[1..], &t[1..]),
+            _ => false
+        }
+    }
+    inner(pattern.as_bytes(), text.as_bytes())
+}
+
+/// A file hashing algorithm supported for comparing local files against what Neocities reports.
+///
+/// Neocities currently only reports sha1 hashes, so that's the only variant.
+/// [`crate::NeocitiesClient::upload_verified`] hashes with `HashAlgo::default()` and compares the
+/// result against the `sha1_hash` field of the re-fetched [`File`] only; no other digest is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashAlgo {
+    #[default]
+    Sha1
+}
+impl HashAlgo {
+    /// Hashes a set of bytes with this algorithm, as a lowercase hex string.
+    pub fn hash_bytes(&self, bytes: impl AsRef<[u8]>) -> String {
+        match self {
+            HashAlgo::Sha1 => {
+                let mut hasher = Sha1::new();
+                hasher.update(bytes);
+                let arr = hasher.finalize();
+                let mut ret = String::new();
+                for b in arr {
+                    ret.push_str(&format!("{:02x}", b))
+                }
+                ret
+            }
+        }
+    }
+    /// Hashes a local file with this algorithm. Returns an error if the file fails to open.
+    pub fn hash_local(&self, path: impl AsRef<Path>) -> Result<String, io::Error> {
+        Ok(self.hash_bytes(read(path)?))
+    }
 }
 
 /// Get the sha1 hash of a local file, as a string. Returns an error if the file fails to open.
 /// A string is used because that's how Neocities provide their hashes, and it's easier to compare
 /// strings than to convert stuff into a big integer type.
 pub fn hash_of_local(path: impl AsRef<Path>) -> Result<String, io::Error> {
-    Ok(hash_of_bytes(read(path)?))
+    HashAlgo::Sha1.hash_local(path)
 }
 /// Get the sha1 hash of a string.
 pub fn hash_of_string(s: impl AsRef<str>) -> String {
-    hash_of_bytes(s.as_ref().as_bytes())
+    HashAlgo::Sha1.hash_bytes(s.as_ref().as_bytes())
 }
 /// Get the sha1 hash of a set of bytes.
 pub fn hash_of_bytes(bytes: impl AsRef<[u8]>) -> String {
-    let mut hasher = Sha1::new();
-    hasher.update(bytes);
-    let arr = hasher.finalize();
-    let mut ret = String::new();
-    for b in arr {
-        ret.push_str(&format!("{:02x}", b))
-    }
-    ret
+    HashAlgo::Sha1.hash_bytes(bytes)
 }
 
-/// Represents a file on the site
-#[derive(Debug)]
-pub struct File {
-    /// Path of the file, from root (eg /index.html)
-    pub path: String,
-    /// Time the file was last modified, in UTC
-    pub modified: chrono::DateTime<Utc>,
-    /// The sha1 hash of the file
-    pub sha1_hash: String,
-    /// The size of the file, in bytes
-    pub size: u64
-}
-impl File {
-    fn from_json(j: &Value) -> Result<File, NeocitiesError> {
-        Ok(File {
-            path: format!("/{}", j.get("path").ok_or(NeocitiesError::ListParseError)?.as_str().ok_or(NeocitiesError::ListParseError)?), // extra / for sanity
-            modified: chrono::DateTime::parse_from_rfc2822(j.get("updated_at").ok_or(NeocitiesError::ListParseError)?.as_str().ok_or(NeocitiesError::ListParseError)?).unwrap().with_timezone(&Utc),
-            sha1_hash: String::from(j.get("sha1_hash").ok_or(NeocitiesError::ListParseError)?.as_str().ok_or(NeocitiesError::ListParseError)?),
-            size: j.get("size").ok_or(NeocitiesError::ListParseError)?.as_u64().ok_or(NeocitiesError::ListParseError)? // if any of this panics don't blame me
-        })
+// `File`, `Dir` and `SiteItem` live in the crate root rather than here, since they back
+// `ListResponse` and are part of the core API surface, not gated behind `site_info`.
+pub use crate::{File, Dir, SiteItem};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_star_matches_any_run() {
+        assert!(glob_match("*.txt", "file.txt"));
+        assert!(glob_match("*.txt", ".txt"));
+        assert!(glob_match("a*b", "ab"));
+        assert!(glob_match("a*b", "axxxb"));
+        assert!(!glob_match("*.txt", "file.html"));
     }
-}
-/// Represents a directory on the site.
-#[derive(Debug)]
-pub struct Dir {
-    /// Path of the directory, from root (eg /blog)
-    pub path: String,
-    /// Time the directory was last modified, in UTC
-    pub modified: chrono::DateTime<Utc>
-}
-impl Dir {
-    fn from_json(j: &Value) -> Result<Dir, NeocitiesError> {
-        Ok(Dir {
-            path: format!("/{}", j.get("path").ok_or(NeocitiesError::ListParseError)?.as_str().ok_or(NeocitiesError::ListParseError)?),
-            modified: chrono::DateTime::parse_from_rfc2822(j.get("updated_at").ok_or(NeocitiesError::ListParseError)?.as_str().ok_or(NeocitiesError::ListParseError)?).unwrap().with_timezone(&Utc),
-        })
+
+    #[test]
+    fn glob_match_question_mark_matches_one_char() {
+        assert!(glob_match("file.?xt", "file.txt"));
+        assert!(!glob_match("file.?xt", "file.xt"));
+        assert!(!glob_match("file.?xt", "file.txxt"));
     }
-}
 
-/// Represents an item on the site.
-#[derive(Debug)]
-pub enum SiteItem {
-    File(File),
-    Dir(Dir)
-}
-impl SiteItem {
-    pub fn get_path(&self) -> &str {
-        match self {
-            SiteItem::Dir(d) => &d.path,
-            SiteItem::File(f) => &f.path
-        }
+    #[test]
+    fn glob_match_is_whole_string() {
+        assert!(!glob_match("file", "file.txt"));
+        assert!(glob_match("*file*", "a/file.txt"));
     }
-    fn from_json(j: &Value) -> Result<SiteItem, NeocitiesError> {
-        Ok(if j.get("is_directory").ok_or(NeocitiesError::ListParseError)?.as_bool().ok_or(NeocitiesError::ListParseError)? {
-            SiteItem::Dir(Dir::from_json(j)?)
-        }
-        else {
-            SiteItem::File(File::from_json(j)?)
-        })
+
+    #[test]
+    fn join_remote_with_root() {
+        assert_eq!(join_remote("site", Path::new("index.html")), "/site/index.html");
+        assert_eq!(join_remote("/site/", Path::new("img/a.png")), "/site/img/a.png");
+    }
+
+    #[test]
+    fn join_remote_with_empty_root() {
+        assert_eq!(join_remote("", Path::new("index.html")), "/index.html");
+        assert_eq!(join_remote("/", Path::new("index.html")), "/index.html");
+    }
+
+    #[test]
+    fn path_under_with_root() {
+        assert!(path_under("/site/index.html", "site"));
+        assert!(path_under("/site/img/a.png", "/site/"));
+        assert!(!path_under("/other/index.html", "site"));
+        assert!(!path_under("/site", "site")); // not actually under it, just a prefix
+    }
+
+    #[test]
+    fn path_under_with_empty_root() {
+        assert!(path_under("/anything.html", ""));
+        assert!(path_under("/anything.html", "/"));
+    }
+
+    #[test]
+    fn load_ignore_patterns_combines_opts_and_file() {
+        let dir = std::env::temp_dir().join(format!("rs_neocities_test_ignore_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".neocitiesignore"), "# comment\n*.log\n\nnode_modules/*\n").unwrap();
+
+        let opts = SyncOptions { ignore: vec![String::from("*.tmp")], ..Default::default() };
+        let patterns = load_ignore_patterns(&dir, &opts);
+
+        assert!(patterns.contains(&String::from("*.tmp")));
+        assert!(patterns.contains(&String::from("*.log")));
+        assert!(patterns.contains(&String::from("node_modules/*")));
+        assert!(!patterns.iter().any(|p| p.starts_with('#')));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_ignore_patterns_with_no_ignore_file() {
+        let dir = std::env::temp_dir().join(format!("rs_neocities_test_empty_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let opts = SyncOptions { ignore: vec![String::from("*.tmp")], ..Default::default() };
+        let patterns = load_ignore_patterns(&dir, &opts);
+
+        assert_eq!(patterns, vec![String::from("*.tmp")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }